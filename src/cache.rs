@@ -0,0 +1,142 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// One crawled page recorded in the cache manifest.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CacheEntry {
+    pub url: String,
+    pub file: String,
+    pub fetched_at: u64,
+    /// Hash of the body as of the last fetch, used to detect unchanged pages
+    /// across scheduled runs without re-reading the stored bodies.
+    pub content_hash: String,
+}
+
+/// Raw-HTML cache used by the `fetch`/`parse` subcommands: bodies are stored on
+/// disk keyed by a hash of their URL, alongside a manifest mapping URL to file
+/// and fetch time, so a crawl can be re-parsed without re-downloading.
+pub struct Cache {
+    dir: PathBuf,
+    manifest_path: PathBuf,
+    manifest: HashMap<String, CacheEntry>,
+}
+
+impl Cache {
+    pub fn open(dir: &str) -> io::Result<Self> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir)?;
+        let manifest_path = dir.join("manifest.json");
+
+        let manifest = if manifest_path.exists() {
+            let raw = fs::read_to_string(&manifest_path)?;
+            serde_json::from_str(&raw).unwrap_or_default()
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self { dir, manifest_path, manifest })
+    }
+
+    fn key_for(url: &str) -> String {
+        Self::hash(url.as_bytes())
+    }
+
+    fn hash(bytes: &[u8]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        format!("{:x}", hasher.finalize())
+    }
+
+    pub fn store(&mut self, url: &str, body: &str) -> io::Result<()> {
+        let file_name = format!("{}.html", Self::key_for(url));
+        fs::write(self.dir.join(&file_name), body)?;
+
+        let fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        self.manifest.insert(url.to_string(), CacheEntry {
+            url: url.to_string(),
+            file: file_name,
+            fetched_at,
+            content_hash: Self::hash(body.as_bytes()),
+        });
+
+        self.save_manifest()
+    }
+
+    /// Whether `body` differs from what was cached for `url` on a previous run
+    /// (an unseen URL counts as changed).
+    pub fn has_changed(&self, url: &str, body: &str) -> bool {
+        match self.manifest.get(url) {
+            Some(entry) => entry.content_hash != Self::hash(body.as_bytes()),
+            None => true,
+        }
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &CacheEntry> {
+        self.manifest.values()
+    }
+
+    pub fn read_body(&self, entry: &CacheEntry) -> io::Result<String> {
+        fs::read_to_string(self.dir.join(&entry.file))
+    }
+
+    fn save_manifest(&self) -> io::Result<()> {
+        let raw = serde_json::to_string_pretty(&self.manifest)
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+
+        fs::write(&self.manifest_path, raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("scraperrs-cache-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn has_changed_is_true_for_an_unseen_url() {
+        let dir = temp_dir("unseen");
+        let cache = Cache::open(dir.to_str().unwrap()).unwrap();
+
+        assert!(cache.has_changed("https://example.com/a", "body"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn has_changed_is_false_once_the_same_body_is_stored() {
+        let dir = temp_dir("unchanged");
+        let mut cache = Cache::open(dir.to_str().unwrap()).unwrap();
+
+        cache.store("https://example.com/a", "body").unwrap();
+        assert!(!cache.has_changed("https://example.com/a", "body"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn has_changed_is_true_once_the_body_differs() {
+        let dir = temp_dir("changed");
+        let mut cache = Cache::open(dir.to_str().unwrap()).unwrap();
+
+        cache.store("https://example.com/a", "body").unwrap();
+        assert!(cache.has_changed("https://example.com/a", "different body"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
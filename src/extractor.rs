@@ -0,0 +1,113 @@
+use indexmap::IndexMap;
+use scraper::{ElementRef, Html, Selector};
+
+use crate::site::SiteConfig;
+
+/// A crawled record, keyed by the column names declared in the site config.
+pub type Record = IndexMap<String, String>;
+
+/// How a site's records and navigation links are pulled out of a parsed page.
+/// `ConfigExtractor` is the only implementation today, driven by a `SiteConfig`,
+/// but the trait exists so a hand-written extractor could be dropped in for a
+/// site whose markup doesn't fit the label/value config shape.
+pub trait Extractor {
+    fn pagination_links(&self, doc: &Html) -> Vec<String>;
+    fn item_links(&self, doc: &Html) -> Vec<String>;
+    fn extract_record(&self, doc: &Html) -> Option<Record>;
+    fn columns(&self) -> &[String];
+}
+
+pub struct ConfigExtractor {
+    config: SiteConfig,
+    pagination_selector: Selector,
+    item_link_selector: Selector,
+    record_selector: Selector,
+    name_selector: Selector,
+    description_selector: Selector,
+    a_selector: Selector,
+}
+
+impl ConfigExtractor {
+    pub fn new(config: SiteConfig) -> Result<Self, String> {
+        let pagination_selector = parse_selector(&config.pagination.selector)?;
+        let item_link_selector = parse_selector(&config.item_links.selector)?;
+        let record_selector = parse_selector(&config.record.selector)?;
+        let name_selector = parse_selector(&config.record.name_selector)?;
+        let description_selector = parse_selector(&config.record.description_selector)?;
+        let a_selector = parse_selector("a")?;
+
+        Ok(Self {
+            config,
+            pagination_selector,
+            item_link_selector,
+            record_selector,
+            name_selector,
+            description_selector,
+            a_selector,
+        })
+    }
+}
+
+impl Extractor for ConfigExtractor {
+    fn pagination_links(&self, doc: &Html) -> Vec<String> {
+        let mut links = vec![];
+
+        for container in doc.select(&self.pagination_selector) {
+            for a in container.select(&self.a_selector) {
+                if let Some(href) = absolute_href(a) {
+                    links.push(href);
+                }
+            }
+        }
+
+        links
+    }
+
+    fn item_links(&self, doc: &Html) -> Vec<String> {
+        doc.select(&self.item_link_selector)
+            .filter_map(absolute_href)
+            .collect()
+    }
+
+    fn extract_record(&self, doc: &Html) -> Option<Record> {
+        let card = doc.select(&self.record_selector).next()?;
+        let mut record = Record::new();
+
+        let name = card
+            .select(&self.name_selector)
+            .map(|e| e.text().collect::<Vec<_>>().join(""))
+            .collect::<String>();
+        record.insert(self.config.record.name_column.clone(), name.trim().to_string());
+
+        for node in card.select(&self.description_selector) {
+            let mut text = node.text().collect::<Vec<_>>().join("");
+
+            for field in &self.config.record.fields {
+                if let Some(index) = text.find(field.label.as_str()) {
+                    let value = text.drain(index + field.label.len()..).collect::<String>();
+                    record.insert(field.column.clone(), value.trim().to_string());
+                }
+            }
+        }
+
+        Some(record)
+    }
+
+    fn columns(&self) -> &[String] {
+        &self.config.columns
+    }
+}
+
+fn parse_selector(selector: &str) -> Result<Selector, String> {
+    Selector::parse(selector).map_err(|err| format!("invalid selector \"{}\": {:?}", selector, err))
+}
+
+fn absolute_href(el: ElementRef) -> Option<String> {
+    let href = el.value().attr("href")?;
+
+    if href.starts_with("http://") || href.starts_with("https://") {
+        Some(href.to_string())
+    } else {
+        None
+    }
+}
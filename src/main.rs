@@ -1,18 +1,63 @@
-use std::{collections::{VecDeque}, time::{Duration, Instant}, error::Error, ops::{Div, Rem}, fs, io};
-use serde::Serialize;
-use scraper::{Html, Selector};
-use clap::Parser;
+mod cache;
+mod extractor;
+mod output;
+mod render;
+mod schedule;
+mod session;
+mod site;
+
+use std::{
+    collections::VecDeque,
+    error::Error,
+    io,
+    ops::{Div, Rem},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+use scraper::Html;
+use clap::{Parser, Subcommand};
+use futures::{stream, StreamExt};
+use rand::Rng;
+use tokio::sync::mpsc;
 use reqwest;
-use csv;
+
+use cache::Cache;
+use extractor::{ConfigExtractor, Extractor};
+use output::{Format, RecordWriter};
+use render::RenderPool;
+use session::Session;
+use site::SiteConfig;
+
+/// Error type shared across tasks so results can travel through the `mpsc` channel.
+pub(crate) type BoxError = Box<dyn Error + Send + Sync>;
 
 #[derive(Parser, Debug)]
 #[command(author, version)]
-struct Args {
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Crawl and extract records in one pass (fetch + parse combined).
+    Auto(AutoArgs),
+    /// Crawl the site and cache raw HTML, without extracting anything.
+    Fetch(FetchArgs),
+    /// Re-run extraction over a previously fetched cache to produce the CSV.
+    Parse(ParseArgs),
+}
+
+#[derive(Parser, Debug)]
+struct CrawlArgs {
     #[arg(short, long)]
     url: String,
 
-    #[arg(short, long, default_value="stdout")]
-    csv: String,
+    #[arg(long, help="Path to a YAML or JSON site config (selectors and fields to extract).")]
+    site: String,
 
     #[arg(short, long, default_value="0", help="Maximum number of records to get, 0: unlimited.")]
     max_records: usize,
@@ -20,221 +65,453 @@ struct Args {
     #[arg(short, long, default_value="30", help="Maximum request timeout.")]
     timeout: usize,
 
-    #[arg(short, long, default_value="3", help="Maximum retries on timeout.")]
-    retries_on_timeout: usize,
+    #[arg(short = 'r', long, default_value="5", help="Maximum attempts per request, the first try included, on retryable failures.")]
+    max_retries: usize,
+
+    #[arg(long, default_value="500", help="Base delay in milliseconds for retry backoff, doubled on each attempt.")]
+    retry_base_ms: u64,
+
+    #[arg(long, default_value="30000", help="Maximum delay in milliseconds between retries.")]
+    retry_max_ms: u64,
+
+    #[arg(long, default_value="10", help="Maximum number of URLs fetched concurrently.")]
+    concurrency: usize,
+
+    #[arg(long, default_value_t = false, help="Render pages with a headless browser via WebDriver instead of a plain GET, for JS-driven sites.")]
+    render: bool,
+
+    #[arg(long, help="WebDriver endpoint (e.g. http://localhost:9515) used when --render is set.")]
+    webdriver_url: Option<String>,
+
+    #[arg(long, help="CSS selector to wait for before reading the rendered page source (used with --render).")]
+    render_wait_selector: Option<String>,
+
+    #[arg(long, help="URL to POST login form fields to before crawling.")]
+    login_url: Option<String>,
+
+    #[arg(long = "login-form-field", value_parser = parse_key_val, help="key=value login form field, repeatable.")]
+    login_form_field: Vec<(String, String)>,
+
+    #[arg(long, help="Cookie jar file: loaded before crawling and saved back after.")]
+    cookies: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-struct Enterprise {
-    name: String,
-    address: String,
-    phone: String,
-    email: String,
-    contact_person: String
+fn parse_key_val(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .ok_or_else(|| format!("invalid key=value pair: \"{}\"", s))
 }
 
-impl Enterprise {
-    fn new() -> Self {
-        Self {
-            name: String::new(),
-            address: String::new(),
-            phone: String::new(),
-            email: String::new(),
-            contact_person: String::new()
-        }
-    }
+#[derive(Parser, Debug)]
+struct AutoArgs {
+    #[command(flatten)]
+    crawl: CrawlArgs,
+
+    #[arg(short, long, default_value="stdout", help="Output file path, or \"stdout\".")]
+    csv: String,
+
+    #[arg(long, value_enum, default_value_t = Format::Csv, help="Output format.")]
+    format: Format,
+
+    #[arg(long, help="Cron expression (e.g. \"0 0 * * * *\"); re-run the crawl on this schedule instead of exiting after one pass.")]
+    schedule: Option<String>,
+
+    #[arg(long, default_value="cache", help="Cache directory used to detect pages unchanged since the previous scheduled run.")]
+    cache: String,
 }
 
-static CSV_COLUMNS_LABALS: [&str; 5] = ["Nombre", "Domicilio", "Teléfono", "Correo electrónico", "Persona de contacto"];
+#[derive(Parser, Debug)]
+struct FetchArgs {
+    #[command(flatten)]
+    crawl: CrawlArgs,
+
+    #[arg(long, default_value="cache", help="Directory to store cached raw HTML and its manifest.")]
+    cache: String,
+}
+
+#[derive(Parser, Debug)]
+struct ParseArgs {
+    #[arg(long, help="Path to a YAML or JSON site config (selectors and fields to extract).")]
+    site: String,
+
+    #[arg(long, default_value="cache", help="Directory holding cached raw HTML and its manifest.")]
+    cache: String,
+
+    #[arg(short, long, default_value="stdout", help="Output file path, or \"stdout\".")]
+    csv: String,
+
+    #[arg(long, value_enum, default_value_t = Format::Csv, help="Output format.")]
+    format: Format,
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
-    let mut urls_to_visit:VecDeque<String> = vec![args.url.clone()].into();
-    let mut url_visited: Vec<String> = vec![];
-    let mut enterprises: Vec<Enterprise> = vec![];
-    let mut csv_writer: csv::Writer<Box<dyn io::Write>>;
-    let begin = Instant::now();
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Auto(args) => run_auto(args).await,
+        Command::Fetch(args) => run_fetch(args).await,
+        Command::Parse(args) => run_parse(args),
+    }
+}
+
+async fn run_auto(args: AutoArgs) -> Result<(), Box<dyn Error>> {
+    match &args.schedule {
+        Some(expr) => run_auto_scheduled(&args, expr).await,
+        None => run_auto_once(&args, &args.csv, None).await,
+    }
+}
+
+/// Repeats the crawl on `expr`'s cron schedule, writing each run to a
+/// timestamped path derived from `--csv` and skipping any page whose body
+/// hasn't changed since the last run (tracked via the cache's content hash).
+async fn run_auto_scheduled(args: &AutoArgs, expr: &str) -> Result<(), Box<dyn Error>> {
+    let cron_schedule = schedule::parse(expr)?;
+
+    loop {
+        let delay = match schedule::next_delay(&cron_schedule) {
+            Some(delay) => delay,
+            None => break,
+        };
+
+        tokio::time::sleep(delay).await;
+
+        let output_path = schedule::timestamped_path(&args.csv);
 
-    csv_writer = csv::WriterBuilder::new()
-        .has_headers(false)
-        .double_quote(true)
-        .quote_style(csv::QuoteStyle::Always)
-        .from_writer({
-            if args.csv == "stdout" {
-                Box::new(io::stdout())
-            } else {
-                Box::new(fs::File::create(args.csv)?)
+        if let Err(err) = run_auto_once(args, &output_path, Some(&args.cache)).await {
+            eprintln!("ERROR during scheduled crawl: {:?}\n", err);
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs one `auto` pass. When `cache_dir` is `Some` (scheduled runs only),
+/// pages whose body hasn't changed since the previous run are skipped via
+/// the cache's content hash; a plain one-shot `auto` leaves the cache
+/// directory untouched and extracts every page it fetches.
+async fn run_auto_once(args: &AutoArgs, output_path: &str, cache_dir: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let extractor = ConfigExtractor::new(SiteConfig::load(&args.crawl.site)?)?;
+    let mut writer = RecordWriter::create(output_path, args.format, extractor.columns())?;
+    let mut cache = cache_dir.map(Cache::open).transpose()?;
+
+    crawl(&args.crawl, &extractor, |url, body, doc| {
+        if let Some(cache) = &mut cache {
+            let changed = cache.has_changed(url, body);
+
+            if let Err(err) = cache.store(url, body) {
+                eprintln!("ERROR caching \"{}\": {:?}\n", url, err);
             }
-        });
+
+            if !changed {
+                return false;
+            }
+        }
+
+        if let Some(record) = extractor.extract_record(doc) {
+            writer.write_record(&record, extractor.columns()).expect("Error writing output record.");
+            true
+        } else {
+            false
+        }
+    }).await?;
+
+    writer.finish()?;
+
+    Ok(())
+}
+
+async fn run_fetch(args: FetchArgs) -> Result<(), Box<dyn Error>> {
+    let extractor = ConfigExtractor::new(SiteConfig::load(&args.crawl.site)?)?;
+    let mut cache = Cache::open(&args.cache)?;
+
+    crawl(&args.crawl, &extractor, |url, body, _doc| {
+        if let Err(err) = cache.store(url, body) {
+            eprintln!("ERROR caching \"{}\": {:?}\n", url, err);
+            false
+        } else {
+            true
+        }
+    }).await
+}
+
+fn run_parse(args: ParseArgs) -> Result<(), Box<dyn Error>> {
+    let extractor = ConfigExtractor::new(SiteConfig::load(&args.site)?)?;
+    let cache = Cache::open(&args.cache)?;
+    let mut writer = RecordWriter::create(&args.csv, args.format, extractor.columns())?;
+
+    for entry in cache.entries() {
+        let body = cache.read_body(entry)?;
+        let doc = Html::parse_document(&body);
+
+        if let Some(record) = extractor.extract_record(&doc) {
+            writer.write_record(&record, extractor.columns())?;
+        }
+    }
+
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Drives a bounded-concurrency crawl of `args.url`, following pagination and
+/// item links discovered by `extractor`. `on_page` is invoked once per
+/// successfully fetched page with its URL, raw body and parsed document; it
+/// returns whether that page should count towards `--max-records`, which lets
+/// `fetch` count cached pages and `auto` count extracted records through the
+/// same loop.
+async fn crawl(
+    args: &CrawlArgs,
+    extractor: &impl Extractor,
+    mut on_page: impl FnMut(&str, &str, &Html) -> bool,
+) -> Result<(), Box<dyn Error>> {
+    let mut urls_to_visit: VecDeque<String> = vec![args.url.clone()].into();
+    let mut url_visited: Vec<String> = vec![];
+    let mut produced: usize = 0;
+    let begin = Instant::now();
+    // The frontier above is owned solely by this coordinator task; workers never
+    // touch it directly and only share this stop flag.
+    let stop = Arc::new(AtomicBool::new(false));
+
+    // Built once and reused for every fetch, carrying cookies across the whole crawl.
+    let session = Session::build(args.cookies.as_deref(), args.login_url.as_deref(), &args.login_form_field).await?;
+
+    // Built once and shared across every fetch so `--render` reuses a bounded
+    // pool of browser sessions instead of launching one per URL.
+    let render_pool = if args.render {
+        match args.webdriver_url.as_deref() {
+            Some(endpoint) => Some(Arc::new(RenderPool::new(endpoint))),
+            None => return Err("--render requires --webdriver-url".into()),
+        }
+    } else {
+        None
+    };
 
     while urls_to_visit.len() > 0 {
-        let url = urls_to_visit.pop_front().unwrap();
+        let batch: Vec<String> = urls_to_visit.drain(..).collect();
+        let (tx, mut rx) = mpsc::channel::<(String, Result<String, BoxError>)>(args.concurrency);
+        let timeout = args.timeout;
+        let max_retries = args.max_retries;
+        let retry_base_ms = args.retry_base_ms;
+        let retry_max_ms = args.retry_max_ms;
+        let concurrency = args.concurrency;
+        let render_wait_selector = args.render_wait_selector.clone();
+        let client = session.client.clone();
+        let render_pool = render_pool.clone();
+        let stop_fetching = stop.clone();
+
+        let fetchers = tokio::spawn(async move {
+            stream::iter(batch.into_iter())
+                .map(|url| {
+                    let tx = tx.clone();
+                    let stop_fetching = stop_fetching.clone();
+                    let render_wait_selector = render_wait_selector.clone();
+                    let client = client.clone();
+                    let render_pool = render_pool.clone();
+                    async move {
+                        if stop_fetching.load(Ordering::Relaxed) {
+                            return;
+                        }
+
+                        let result = match &render_pool {
+                            Some(pool) => pool.render(&url, render_wait_selector.as_deref(), timeout).await,
+                            None => scrap_url(&client, &url, timeout, max_retries, retry_base_ms, retry_max_ms).await,
+                        };
+
+                        let _ = tx.send((url, result)).await;
+                    }
+                })
+                .buffer_unordered(concurrency)
+                .for_each(|_| async {})
+                .await;
+        });
 
-        match scrap_url(&url, args.timeout, args.retries_on_timeout).await {
-            Ok(body) => {
-                let mut links = Vec::new();
-                links.append(&mut extract_pagination_links(&body));
-                links.append(&mut extract_enterprise_links(&body));
+        while let Some((url, result)) = rx.recv().await {
+            match result {
+                Ok(body) => {
+                    let doc = Html::parse_document(&body);
+                    let mut links = Vec::new();
+                    links.append(&mut extractor.pagination_links(&doc));
+                    links.append(&mut extractor.item_links(&doc));
+
+                    for link in links {
+                        if !url_visited.contains(&link) && !urls_to_visit.contains(&link){
+                            urls_to_visit.push_back(link);
+                        }
+                    }
 
-                for link in links {
-                    if !url_visited.contains(&link) && !urls_to_visit.contains(&link){
-                        urls_to_visit.push_back(link);
+                    if on_page(&url, &body, &doc) {
+                        produced += 1;
                     }
                 }
-
-                if let Some(enterprise) = extract_enterprise_data(&body) {
-                    enterprises.push(enterprise);
+                Err(err) => {
+                    eprintln!("ERROR on \"{}\": {:?}\n", url, err);
                 }
             }
-            Err(err) => {
-                eprintln!("ERROR on \"{}\": {:?}\n", url, err);
-            }
-        }
 
-        url_visited.push(url);
+            url_visited.push(url);
 
-        if urls_to_visit.len() > 0 {
-            let q = url_visited.len() as f32 / (url_visited.len() + urls_to_visit.len()) as f32 * 100f32;
+            let remaining = urls_to_visit.len();
+            let q = url_visited.len() as f32 / (url_visited.len() + remaining) as f32 * 100f32;
             let elapse = Instant::now() - begin;
             let minutes = elapse.as_secs().div(60);
             let seconds = elapse.as_secs().rem(60);
             eprint!(
-                "Done {}/{} ({:.2}%) URLs, found {} enterprises on {}m / {}s\t\t\r",
+                "Done {}/{} ({:.2}%) URLs, produced {} records on {}m / {}s\t\t\r",
                 url_visited.len(),
-                url_visited.len() + urls_to_visit.len(),
+                url_visited.len() + remaining,
                 &q,
-                enterprises.len(),
+                produced,
                 minutes,
                 seconds
             );
+
+            if args.max_records > 0 && produced >= args.max_records {
+                eprintln!("\nReached maximum number of records for scrapping: {}", args.max_records);
+                // Stop handing out new work; in-flight fetches are left to drain below.
+                stop.store(true, Ordering::Relaxed);
+                break;
+            }
         }
 
-        if args.max_records > 0 && enterprises.len() >= args.max_records {
-            eprintln!("\nReached maximum number of records for scrapping: {}", args.max_records);
+        // Closing the receiver lets any fetch still waiting to send finish at once.
+        drop(rx);
+        fetchers.await?;
+
+        if stop.load(Ordering::Relaxed) {
             break;
         }
     }
 
-    if enterprises.len() > 0 {
-        csv_writer.write_record(&CSV_COLUMNS_LABALS).expect("Error writing to CSV file.");
-        for ent in enterprises {
-            csv_writer.serialize(ent).expect("Error writing to CSV file.");
+    session.save()?;
+
+    if let Some(pool) = render_pool {
+        if let Ok(pool) = Arc::try_unwrap(pool) {
+            pool.quit().await;
         }
     }
 
     Ok(())
 }
 
-async fn scrap_url(url: &String, timeout: usize, retries_on_timeout: usize) ->  Result<String, Box<dyn Error>> {
-    let client = reqwest::Client::new();
-
+async fn scrap_url(
+    client: &reqwest::Client,
+    url: &String,
+    timeout: usize,
+    max_retries: usize,
+    retry_base_ms: u64,
+    retry_max_ms: u64,
+) ->  Result<String, BoxError> {
     assert!(timeout > 0);
-    assert!(retries_on_timeout > 0);
+    assert!(max_retries > 0);
+
+    let mut last_err: Option<BoxError> = None;
 
-    for _ in 0..retries_on_timeout {
+    for attempt in 0..max_retries {
         let future = client
             .get(url)
             .header("User-Agent", "Mozilla 5.0")
             .timeout(Duration::from_secs(timeout as u64))
             .send();
-        
-        match future.await {
-            Ok(response) => return Ok(response.text().await.unwrap()),
-            Err(err) if err.is_timeout() => continue,
-            Err(err) => return Err(Box::new(err))
-        }
-    }
 
-    Err(
-        Box::new(
-            io::Error::new(
-                io::ErrorKind::TimedOut,
-                "Timeout"
-            )
-        )
-    )
-}
-
-fn extract_pagination_links (body: &String) -> Vec<String> {
-    let doc = Html::parse_document(body);
-    let ul_paginator_selector = Selector::parse(r#"ul[class="pager lfr-pagination-buttons"]"#).unwrap();
-    let a_paginator_selector = Selector::parse(r#"a"#).unwrap();
-    let mut links = vec![];
-
-    for ul in doc.select(&ul_paginator_selector) {
-        for a in ul.select(&a_paginator_selector) {
-            let href = a.value().attr("href").unwrap_or("");
-
-            if ["http://", "https://"].map(|p| href.starts_with(p))
-                .into_iter()
-                .reduce(|acc, e| acc || e)
-                .unwrap()
-            {
-                links.push(href.to_string());
+        match future.await {
+            Ok(response) if response.status().is_success() => {
+                match response.text().await {
+                    Ok(body) => return Ok(body),
+                    Err(err) if err.is_timeout() || err.is_connect() || err.is_body() || err.is_decode() => {
+                        last_err = Some(Box::new(err));
+
+                        if attempt + 1 < max_retries {
+                            backoff_sleep(attempt as u32, retry_base_ms, retry_max_ms, None).await;
+                        }
+                    }
+                    Err(err) => return Err(Box::new(err)),
+                }
             }
-        }
-    }
+            Ok(response) => {
+                let status = response.status();
 
-    links
-}
+                if !is_retryable_status(status) {
+                    return Err(format!("HTTP {}", status).into());
+                }
 
-fn extract_enterprise_links (body: &String) -> Vec<String> {
-    let doc = Html::parse_document(body);
-    let a_selector = Selector::parse(r#"a[class="lm"]"#).unwrap();
-    let mut links = vec![];
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .map(Duration::from_secs);
 
-    for a in doc.select(&a_selector) {
-        let href = a.value().attr("href").unwrap_or("");
+                last_err = Some(format!("HTTP {}", status).into());
 
-        if ["http://", "https://"].map(|p| href.starts_with(p))
-            .into_iter()
-            .reduce(|acc, e| acc || e)
-            .unwrap()
-        {
-            links.push(href.to_string());
+                if attempt + 1 < max_retries {
+                    backoff_sleep(attempt as u32, retry_base_ms, retry_max_ms, retry_after).await;
+                }
+            }
+            Err(err) if err.is_timeout() || err.is_connect() => {
+                last_err = Some(Box::new(err));
+
+                if attempt + 1 < max_retries {
+                    backoff_sleep(attempt as u32, retry_base_ms, retry_max_ms, None).await;
+                }
+            }
+            Err(err) => return Err(Box::new(err))
         }
     }
 
-    links
+    Err(last_err.unwrap_or_else(|| Box::new(io::Error::new(io::ErrorKind::TimedOut, "Timeout"))))
 }
 
-fn extract_enterprise_data (body: &String) -> Option<Enterprise> {
-    let doc = Html::parse_document(body);
-    let card_selector = Selector::parse(r#"div[class="socios-panel-lat"]"#).unwrap();
-    let name_selector = Selector::parse(r#"h2[class="tit-soc"]"#).unwrap();
-    let description_selector = Selector::parse(r#"div[class="socios-descripcion"]"#).unwrap();
-
-    if let Some(card) = doc.select(&card_selector).collect::<Vec<_>>().first() {
-        let mut enterprise = Enterprise::new();
-
-        enterprise.name = String::from(card
-            .select(&name_selector)
-            .map(|e| e.text().collect::<Vec<_>>().join(""))
-            .collect::<String>().trim());
-        
-        for node in card.select(&description_selector) {
-            let mut text = node.text().collect::<Vec<_>>().join("");
+/// Retryable HTTP statuses: the usual transient ones. Connection errors and
+/// timeouts are classified separately in `scrap_url`.
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
 
-            if let Some(index)= text.find(&"Domicilio") {
-                enterprise.address = String::from(text.drain(index + "Domicilio".len()..).collect::<String>().trim());
-            }
+/// Sleeps `base_ms * 2^attempt` (capped at `max_ms`) plus uniform jitter in `[0, delay/2)`,
+/// or honors a server-provided `Retry-After` when present.
+async fn backoff_sleep(attempt: u32, base_ms: u64, max_ms: u64, retry_after: Option<Duration>) {
+    let delay = match retry_after {
+        Some(delay) => delay,
+        None => {
+            let exp = base_ms.saturating_mul(1u64 << attempt.min(20)).min(max_ms);
+            let jitter_bound = (exp / 2).max(1);
+            let jitter = rand::thread_rng().gen_range(0..jitter_bound);
+            Duration::from_millis(exp.saturating_add(jitter))
+        }
+    };
 
-            if let Some(index)= text.find(&"Teléfono") {
-                enterprise.phone = String::from(text.drain(index + "Teléfono".len()..).collect::<String>().trim());
-            }
+    tokio::time::sleep(delay).await;
+}
 
-            if let Some(index)= text.find(&"Correo electrónico") {
-                enterprise.email = String::from(text.drain(index + "Correo electrónico".len()..).collect::<String>().trim());
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_retryable_status_matches_transient_codes() {
+        assert!(is_retryable_status(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(reqwest::StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable_status(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(is_retryable_status(reqwest::StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(reqwest::StatusCode::GATEWAY_TIMEOUT));
+    }
 
-            if let Some(index)= text.find(&"Persona de contacto") {
-                enterprise.contact_person = String::from(text.drain(index + "Persona de contacto".len()..).collect::<String>().trim());
-            }
-        }
+    #[test]
+    fn is_retryable_status_rejects_other_codes() {
+        assert!(!is_retryable_status(reqwest::StatusCode::OK));
+        assert!(!is_retryable_status(reqwest::StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(reqwest::StatusCode::UNAUTHORIZED));
+    }
 
-        return Some(enterprise);
+    #[test]
+    fn parse_key_val_splits_on_first_equals() {
+        assert_eq!(parse_key_val("key=value").unwrap(), ("key".to_string(), "value".to_string()));
+        assert_eq!(parse_key_val("key=a=b").unwrap(), ("key".to_string(), "a=b".to_string()));
     }
 
-    None
+    #[test]
+    fn parse_key_val_rejects_missing_equals() {
+        assert!(parse_key_val("no-equals-here").is_err());
+    }
 }
@@ -0,0 +1,147 @@
+use std::{
+    fs,
+    io::{self, Write},
+};
+
+use clap::ValueEnum;
+use indexmap::IndexMap;
+
+use crate::extractor::Record;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Csv,
+    Json,
+    Ndjson,
+}
+
+enum Inner {
+    Csv(csv::Writer<Box<dyn Write>>),
+    Json { out: Box<dyn Write>, wrote_any: bool },
+    Ndjson(Box<dyn Write>),
+}
+
+/// Writes each record out as soon as it's extracted and flushes immediately,
+/// so an interrupted crawl keeps its partial results and memory stays bounded
+/// on large sites, instead of buffering everything into a `Vec` until the end.
+pub struct RecordWriter {
+    inner: Inner,
+}
+
+impl RecordWriter {
+    pub fn create(path: &str, format: Format, columns: &[String]) -> io::Result<Self> {
+        let inner = match format {
+            Format::Csv => {
+                let mut writer = csv::WriterBuilder::new()
+                    .has_headers(false)
+                    .double_quote(true)
+                    .quote_style(csv::QuoteStyle::Always)
+                    .from_writer(open(path)?);
+                writer.write_record(columns)?;
+                writer.flush()?;
+                Inner::Csv(writer)
+            }
+            Format::Json => {
+                let mut out = open(path)?;
+                out.write_all(b"[")?;
+                Inner::Json { out, wrote_any: false }
+            }
+            Format::Ndjson => Inner::Ndjson(open(path)?),
+        };
+
+        Ok(Self { inner })
+    }
+
+    pub fn write_record(&mut self, record: &Record, columns: &[String]) -> io::Result<()> {
+        match &mut self.inner {
+            Inner::Csv(writer) => {
+                let row: Vec<&str> = columns
+                    .iter()
+                    .map(|column| record.get(column).map(String::as_str).unwrap_or(""))
+                    .collect();
+                writer.write_record(&row)?;
+                writer.flush()?;
+            }
+            Inner::Json { out, wrote_any } => {
+                let record = normalize(record, columns);
+
+                if *wrote_any {
+                    out.write_all(b",")?;
+                }
+                serde_json::to_writer(&mut *out, &record).map_err(to_io_error)?;
+                out.flush()?;
+                *wrote_any = true;
+            }
+            Inner::Ndjson(out) => {
+                let record = normalize(record, columns);
+
+                serde_json::to_writer(&mut *out, &record).map_err(to_io_error)?;
+                out.write_all(b"\n")?;
+                out.flush()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Closes off formats that need a trailing delimiter (the `json` array).
+    pub fn finish(mut self) -> io::Result<()> {
+        if let Inner::Json { out, .. } = &mut self.inner {
+            out.write_all(b"]")?;
+            out.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Fills in an empty value for every declared column missing from `record`, so
+/// `json`/`ndjson` output has the same schema-consistent field set as `csv`
+/// regardless of which selectors happened to match on a given page.
+fn normalize(record: &Record, columns: &[String]) -> Record {
+    columns
+        .iter()
+        .map(|column| (column.clone(), record.get(column).cloned().unwrap_or_default()))
+        .collect::<IndexMap<_, _>>()
+}
+
+fn open(path: &str) -> io::Result<Box<dyn Write>> {
+    if path == "stdout" {
+        Ok(Box::new(io::stdout()))
+    } else {
+        Ok(Box::new(fs::File::create(path)?))
+    }
+}
+
+fn to_io_error(err: serde_json::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_fills_missing_columns_with_empty_string() {
+        let mut record = Record::new();
+        record.insert("name".to_string(), "widget".to_string());
+
+        let columns = vec!["name".to_string(), "price".to_string()];
+        let normalized = normalize(&record, &columns);
+
+        assert_eq!(normalized.get("name").map(String::as_str), Some("widget"));
+        assert_eq!(normalized.get("price").map(String::as_str), Some(""));
+    }
+
+    #[test]
+    fn normalize_orders_by_declared_columns() {
+        let mut record = Record::new();
+        record.insert("price".to_string(), "9.99".to_string());
+        record.insert("name".to_string(), "widget".to_string());
+
+        let columns = vec!["name".to_string(), "price".to_string()];
+        let normalized = normalize(&record, &columns);
+
+        assert_eq!(normalized.keys().collect::<Vec<_>>(), vec!["name", "price"]);
+    }
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use thirtyfour::prelude::*;
+use tokio::sync::Mutex;
+
+use crate::BoxError;
+
+/// Pool of headless-browser sessions for `--render`, keyed to a single
+/// `webdriver_url`. Sessions are checked out for a fetch and returned to the
+/// idle pool afterwards instead of being opened and torn down per page, so
+/// `--render` with `--concurrency` reuses at most `concurrency` sessions
+/// rather than launching one browser per URL.
+pub struct RenderPool {
+    webdriver_url: String,
+    idle: Mutex<Vec<WebDriver>>,
+}
+
+impl RenderPool {
+    pub fn new(webdriver_url: &str) -> Self {
+        Self {
+            webdriver_url: webdriver_url.to_string(),
+            idle: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Renders `url` using an idle session from the pool, opening a new one
+    /// only when none is available, and returns it to the pool afterwards.
+    pub async fn render(
+        &self,
+        url: &str,
+        wait_selector: Option<&str>,
+        timeout: usize,
+    ) -> Result<String, BoxError> {
+        let driver = self.checkout().await?;
+        let result = render(&driver, url, wait_selector, timeout).await;
+        self.checkin(driver).await;
+        result
+    }
+
+    async fn checkout(&self) -> Result<WebDriver, BoxError> {
+        if let Some(driver) = self.idle.lock().await.pop() {
+            return Ok(driver);
+        }
+
+        Ok(WebDriver::new(&self.webdriver_url, DesiredCapabilities::chrome()).await?)
+    }
+
+    async fn checkin(&self, driver: WebDriver) {
+        self.idle.lock().await.push(driver);
+    }
+
+    /// Closes every idle session once the crawl is done with the pool.
+    pub async fn quit(self) {
+        for driver in self.idle.into_inner() {
+            let _ = driver.quit().await;
+        }
+    }
+}
+
+async fn render(
+    driver: &WebDriver,
+    url: &str,
+    wait_selector: Option<&str>,
+    timeout: usize,
+) -> Result<String, BoxError> {
+    driver.goto(url).await?;
+
+    if let Some(selector) = wait_selector {
+        driver
+            .query(By::Css(selector))
+            .wait(Duration::from_secs(timeout as u64), Duration::from_millis(250))
+            .first()
+            .await?;
+    }
+
+    Ok(driver.source().await?)
+}
@@ -0,0 +1,90 @@
+use std::{path::Path, str::FromStr, time::Duration};
+
+use chrono::Utc;
+use cron::Schedule;
+
+use crate::BoxError;
+
+/// Parses a cron expression (as accepted by the `cron` crate, e.g. `"0 0 * * * *"`).
+pub fn parse(expr: &str) -> Result<Schedule, BoxError> {
+    Schedule::from_str(expr).map_err(|err| -> BoxError { err.to_string().into() })
+}
+
+/// Delay until `schedule`'s next fire time, or `None` if it has none left.
+pub fn next_delay(schedule: &Schedule) -> Option<Duration> {
+    let now = Utc::now();
+    schedule
+        .upcoming(Utc)
+        .next()
+        .and_then(|next| (next - now).to_std().ok())
+}
+
+/// Derives a per-run output path from the base `--csv` path by inserting a
+/// UTC timestamp before the extension, e.g. `out.csv` -> `out-20260730T120000Z.csv`.
+pub fn timestamped_path(base: &str) -> String {
+    if base == "stdout" {
+        return base.to_string();
+    }
+
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+    let path = Path::new(base);
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty());
+    let file_name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+
+    let stamped_name = match file_name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}-{}.{}", stem, stamp, ext),
+        None => format!("{}-{}", file_name, stamp),
+    };
+
+    match parent {
+        Some(parent) => parent.join(stamped_name).to_string_lossy().into_owned(),
+        None => stamped_name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Timestamp format is `%Y%m%dT%H%M%SZ`: 8 digits, `T`, 6 digits, `Z`.
+    fn is_stamp(s: &str) -> bool {
+        s.len() == 16
+            && s.as_bytes()[8] == b'T'
+            && s.as_bytes()[15] == b'Z'
+            && s[0..8].bytes().all(|b| b.is_ascii_digit())
+            && s[9..15].bytes().all(|b| b.is_ascii_digit())
+    }
+
+    #[test]
+    fn timestamped_path_inserts_stamp_before_the_extension() {
+        let path = timestamped_path("out.csv");
+        let (stem, rest) = path.split_once('-').unwrap();
+        let (stamp, ext) = rest.split_once('.').unwrap();
+
+        assert_eq!(stem, "out");
+        assert!(is_stamp(stamp), "not a timestamp: {}", stamp);
+        assert_eq!(ext, "csv");
+    }
+
+    #[test]
+    fn timestamped_path_only_splits_the_file_name_extension() {
+        let path = timestamped_path("dir.with.dots/out.csv");
+
+        assert!(path.starts_with("dir.with.dots/out-"), "{}", path);
+        assert!(path.ends_with(".csv"), "{}", path);
+    }
+
+    #[test]
+    fn timestamped_path_handles_no_extension() {
+        let path = timestamped_path("out");
+        let (stem, stamp) = path.split_once('-').unwrap();
+
+        assert_eq!(stem, "out");
+        assert!(is_stamp(stamp), "not a timestamp: {}", stamp);
+    }
+
+    #[test]
+    fn timestamped_path_passes_stdout_through_unchanged() {
+        assert_eq!(timestamped_path("stdout"), "stdout");
+    }
+}
@@ -0,0 +1,65 @@
+use std::{fs, io, path::Path, sync::Arc};
+
+use cookie_store::CookieStore;
+use reqwest::Client;
+use reqwest_cookie_store::CookieStoreMutex;
+
+use crate::BoxError;
+
+/// A `reqwest::Client` built once and reused across every fetch in a crawl,
+/// carrying a persistent cookie jar and (optionally) an authenticated session
+/// established by POSTing a login form before the crawl loop starts.
+pub struct Session {
+    pub client: Client,
+    cookie_store: Arc<CookieStoreMutex>,
+    cookies_path: Option<String>,
+}
+
+impl Session {
+    pub async fn build(
+        cookies_path: Option<&str>,
+        login_url: Option<&str>,
+        login_form_fields: &[(String, String)],
+    ) -> Result<Self, BoxError> {
+        let cookie_store = match cookies_path {
+            Some(path) if Path::new(path).exists() => {
+                let reader = io::BufReader::new(fs::File::open(path)?);
+                CookieStore::load_json(reader).map_err(|err| -> BoxError { err.into() })?
+            }
+            _ => CookieStore::default(),
+        };
+        let cookie_store = Arc::new(CookieStoreMutex::new(cookie_store));
+
+        let client = Client::builder()
+            .cookie_provider(cookie_store.clone())
+            .build()?;
+
+        if let Some(login_url) = login_url {
+            let form: Vec<(&str, &str)> = login_form_fields
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str()))
+                .collect();
+
+            client.post(login_url).form(&form).send().await?;
+        }
+
+        Ok(Self {
+            client,
+            cookie_store,
+            cookies_path: cookies_path.map(String::from),
+        })
+    }
+
+    /// Saves the jar back to disk, picking up any cookies set during the crawl.
+    pub fn save(&self) -> Result<(), BoxError> {
+        let Some(path) = &self.cookies_path else {
+            return Ok(());
+        };
+
+        let store = self.cookie_store.lock().map_err(|err| err.to_string())?;
+        let mut writer = io::BufWriter::new(fs::File::create(path)?);
+        store.save_json(&mut writer).map_err(|err| -> BoxError { err.into() })?;
+
+        Ok(())
+    }
+}
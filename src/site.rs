@@ -0,0 +1,53 @@
+use std::{error::Error, fs, path::Path};
+
+use serde::Deserialize;
+
+/// A site definition: the CSS selectors and field layout needed to crawl one
+/// directory. Loaded from a YAML or JSON file passed via `--site`, so adding a
+/// new target is a matter of writing a config rather than editing Rust.
+#[derive(Debug, Deserialize)]
+pub struct SiteConfig {
+    pub name: String,
+    pub pagination: SelectorConfig,
+    pub item_links: SelectorConfig,
+    pub record: RecordConfig,
+    /// CSV column order; also used as the written header row.
+    pub columns: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SelectorConfig {
+    pub selector: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RecordConfig {
+    /// Selector for the element holding one record (e.g. one enterprise card).
+    pub selector: String,
+    pub name_selector: String,
+    /// Column the name goes into.
+    pub name_column: String,
+    /// Selector for the element(s) whose text is label-split into fields.
+    pub description_selector: String,
+    pub fields: Vec<FieldConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FieldConfig {
+    /// Text label preceding the value inside `description_selector`, e.g. "Domicilio".
+    pub label: String,
+    /// Column the value is stored under.
+    pub column: String,
+}
+
+impl SiteConfig {
+    pub fn load(path: &str) -> Result<Self, Box<dyn Error>> {
+        let raw = fs::read_to_string(path)?;
+
+        if Path::new(path).extension().and_then(|ext| ext.to_str()) == Some("json") {
+            Ok(serde_json::from_str(&raw)?)
+        } else {
+            Ok(serde_yaml::from_str(&raw)?)
+        }
+    }
+}